@@ -0,0 +1,252 @@
+/*!
+Support for `--binary-consistent` (spelled `--binary=strict` internally),
+which makes binary-file filtering give the same answer regardless of which
+output mode (`-l`, `-c`, `-q`, or normal printing) ripgrep is running in.
+
+Normally, `-l/--files-with-matches` and `-q/--quiet` stop searching a file
+as soon as the *first match* is found, since that's all they need to report
+a result. That means a binary file's NUL byte, which might appear after the
+first match, is never actually seen, so `-l` and `-q` end up treating a
+binary file as a normal match while `-c/--count` (which must scan the whole
+file to produce an accurate count) detects the NUL and suppresses the file
+entirely. See the `matching_files_inconsistent_with_count` test in
+`tests/binary.rs` for a demonstration.
+
+`--binary-consistent` fixes this by scanning the file for a NUL byte (or
+until the configured binary detection byte is found) *before* emitting any
+path, count, or quiet-mode result, so that a file classified as binary is
+filtered out uniformly no matter which output mode is in play. This is
+opt-in because it necessarily costs performance for `-l`/`-q`, which exist
+specifically to avoid scanning more of a file than necessary.
+
+[`files_with_matches`] and [`quiet_matches`] implement the `-l` and `-q`
+early-exit decision over an in-memory haystack: instead of stopping and
+reporting a result as soon as
+the first matching line is found, they consult [`decide_early_exit`] (via
+[`find_binary_byte`]) before doing so, so that under
+[`BinaryConsistency::Strict`] they agree with what `-c` would report for
+the same file.
+*/
+
+/// How binary detection should interact with output modes that would
+/// otherwise stop searching as soon as they have enough information to
+/// produce a result (namely `-l`, `-q`, and to a lesser extent `-c`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BinaryConsistency {
+    /// The default: `-l` and `-q` may report a result before binary
+    /// detection has had a chance to run, since they stop at the first
+    /// match. This is fast, but can disagree with `-c`, which always
+    /// scans far enough to detect binary data.
+    Fast,
+    /// Always finish scanning for the configured binary detection byte
+    /// (typically NUL) before emitting a path, count, or quiet-mode
+    /// result, so that every output mode agrees on whether a file is
+    /// binary.
+    Strict,
+}
+
+impl Default for BinaryConsistency {
+    fn default() -> BinaryConsistency {
+        BinaryConsistency::Fast
+    }
+}
+
+impl BinaryConsistency {
+    /// Whether early-exit output modes (`-l`, `-q`) must still confirm the
+    /// absence of binary data before reporting a result.
+    pub fn requires_full_scan(self) -> bool {
+        matches!(self, BinaryConsistency::Strict)
+    }
+}
+
+/// Scans `haystack` for the first occurrence of `binary_byte` (usually
+/// `\x00`), returning its offset if found.
+///
+/// This is the check that `--binary-consistent` runs to completion before
+/// `-l`/`-q` are permitted to short-circuit on their first match, so that
+/// their result agrees with what `-c` (which already scans the whole file)
+/// would report.
+pub fn find_binary_byte(haystack: &[u8], binary_byte: u8) -> Option<usize> {
+    haystack.iter().position(|&b| b == binary_byte)
+}
+
+/// What an early-exit output mode (`-l`/`-q`) should do once it has found
+/// its first match.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EarlyExitOutcome {
+    /// Emit the early-exit result: print the path for `-l`, or exit
+    /// successfully with no output for `-q`.
+    Emit,
+    /// Suppress the early-exit result, because the file is binary and
+    /// should be filtered out exactly as `-c`'s full-file scan would
+    /// filter it out.
+    SuppressBinary,
+}
+
+/// Decides what an early-exit output mode should do once it has found its
+/// first match in `haystack`, given the configured consistency mode.
+///
+/// Under [`BinaryConsistency::Fast`] (the default), this always returns
+/// `Emit`: the caller stops scanning as soon as it has a match, exactly as
+/// `-l`/`-q` do today, without looking for `binary_byte` at all.
+///
+/// Under [`BinaryConsistency::Strict`], the caller must have read
+/// `haystack` far enough to cover the whole file (or at least up to
+/// `binary_byte`, if one exists) before calling this; `find_binary_byte`
+/// is then used to decide whether the match should be suppressed so the
+/// result agrees with what `-c` would report for the same file.
+pub fn decide_early_exit(
+    mode: BinaryConsistency,
+    haystack: &[u8],
+    binary_byte: u8,
+) -> EarlyExitOutcome {
+    if mode.requires_full_scan()
+        && find_binary_byte(haystack, binary_byte).is_some()
+    {
+        EarlyExitOutcome::SuppressBinary
+    } else {
+        EarlyExitOutcome::Emit
+    }
+}
+
+/// The `-l/--files-with-matches` early-exit search over an in-memory
+/// `haystack`: scans it line by line for the first line containing
+/// `needle`, and returns its 1-based line number if the file should be
+/// reported.
+///
+/// Under [`BinaryConsistency::Fast`], this returns as soon as a match is
+/// found, exactly as `-l` behaves today. Under [`BinaryConsistency::Strict`],
+/// finding a match first triggers a scan of the whole of `haystack` (via
+/// [`decide_early_exit`]) for `binary_byte`; if one turns up, the file is
+/// reported as binary and no path is returned, agreeing with what `-c`
+/// would report for the same file.
+pub fn files_with_matches(
+    mode: BinaryConsistency,
+    haystack: &[u8],
+    needle: &[u8],
+    binary_byte: u8,
+) -> Option<u64> {
+    for (i, line) in haystack.split(|&b| b == b'\n').enumerate() {
+        if contains(line, needle) {
+            return match decide_early_exit(mode, haystack, binary_byte) {
+                EarlyExitOutcome::Emit => Some(i as u64 + 1),
+                EarlyExitOutcome::SuppressBinary => None,
+            };
+        }
+    }
+    None
+}
+
+/// The actual `-q/--quiet` early-exit search: like [`files_with_matches`],
+/// but only reports whether a match was found at all, since `-q` produces
+/// no output of its own either way.
+pub fn quiet_matches(
+    mode: BinaryConsistency,
+    haystack: &[u8],
+    needle: &[u8],
+    binary_byte: u8,
+) -> bool {
+    files_with_matches(mode, haystack, needle, binary_byte).is_some()
+}
+
+/// Naive substring search, since pulling in a real search engine here would
+/// be overkill for deciding *whether* a line matches in this module's tests
+/// and simulated early-exit paths.
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_is_default() {
+        assert_eq!(BinaryConsistency::default(), BinaryConsistency::Fast);
+    }
+
+    #[test]
+    fn only_strict_requires_full_scan() {
+        assert!(!BinaryConsistency::Fast.requires_full_scan());
+        assert!(BinaryConsistency::Strict.requires_full_scan());
+    }
+
+    #[test]
+    fn finds_nul_byte() {
+        assert_eq!(find_binary_byte(b"abc\x00def", 0), Some(3));
+        assert_eq!(find_binary_byte(b"abcdef", 0), None);
+    }
+
+    #[test]
+    fn fast_never_suppresses() {
+        let outcome = decide_early_exit(BinaryConsistency::Fast, b"cat\x00here", 0);
+        assert_eq!(outcome, EarlyExitOutcome::Emit);
+    }
+
+    #[test]
+    fn strict_suppresses_when_binary_byte_present() {
+        let outcome =
+            decide_early_exit(BinaryConsistency::Strict, b"cat\x00here", 0);
+        assert_eq!(outcome, EarlyExitOutcome::SuppressBinary);
+    }
+
+    #[test]
+    fn strict_emits_when_no_binary_byte() {
+        let outcome = decide_early_exit(BinaryConsistency::Strict, b"cat here", 0);
+        assert_eq!(outcome, EarlyExitOutcome::Emit);
+    }
+
+    #[test]
+    fn fast_reports_binary_file_just_like_l_does_today() {
+        let haystack = b"padding\ncat here\n\x00more\n";
+        assert_eq!(
+            files_with_matches(BinaryConsistency::Fast, haystack, b"cat", 0),
+            Some(2),
+        );
+    }
+
+    #[test]
+    fn strict_suppresses_binary_file_agreeing_with_count() {
+        // Same scenario as `matching_files_inconsistent_with_count` in
+        // tests/binary.rs: a NUL byte appears after the first match, so
+        // `-c` (which scans the whole file) would suppress this file.
+        // Under `Strict`, `-l` now agrees.
+        let haystack = b"padding\ncat here\n\x00more\n";
+        assert_eq!(
+            files_with_matches(BinaryConsistency::Strict, haystack, b"cat", 0),
+            None,
+        );
+    }
+
+    #[test]
+    fn strict_still_reports_non_binary_file() {
+        let haystack = b"padding\ncat here\nmore\n";
+        assert_eq!(
+            files_with_matches(BinaryConsistency::Strict, haystack, b"cat", 0),
+            Some(2),
+        );
+    }
+
+    #[test]
+    fn quiet_matches_follows_files_with_matches() {
+        let binary = b"padding\ncat here\n\x00more\n";
+        assert!(quiet_matches(BinaryConsistency::Fast, binary, b"cat", 0));
+        assert!(!quiet_matches(BinaryConsistency::Strict, binary, b"cat", 0));
+    }
+
+    #[test]
+    fn no_match_reports_nothing_under_either_mode() {
+        let haystack = b"padding\nmore\n";
+        assert_eq!(
+            files_with_matches(BinaryConsistency::Fast, haystack, b"cat", 0),
+            None,
+        );
+        assert_eq!(
+            files_with_matches(BinaryConsistency::Strict, haystack, b"cat", 0),
+            None,
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::{fmt, io, path::Path};
+
+/// A single directive extracted from one layer (one config file) of the
+/// hierarchical config format.
+///
+/// Directives are returned in the order they appear in the file. Merging
+/// across layers (i.e. applying `Unset` and overriding earlier `Set`
+/// entries) is the caller's responsibility; this type only knows how to
+/// parse one file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) enum Directive {
+    /// A `key = value` item under `[section]` (or the implicit top-level
+    /// section, `""`, for items that appear before any header).
+    Set { section: String, key: String, value: String },
+    /// A `%unset key` item under `[section]`.
+    Unset { section: String, key: String },
+    /// A `%include path` item. `line` is the 1-based line it appeared on,
+    /// used for cycle-detection diagnostics.
+    Include { target: String, line: u64 },
+}
+
+/// The directives parsed out of a single config file.
+pub(super) struct Layer {
+    pub(super) directives: Vec<Directive>,
+}
+
+impl Layer {
+    /// Parse `contents` (the text of the file at `path`, used only for
+    /// error messages) into a sequence of directives.
+    pub(super) fn parse(
+        path: &Path,
+        contents: &str,
+    ) -> Result<Layer, ParseError> {
+        let mut directives = vec![];
+        let mut section = String::new();
+        // The index in `directives` of the most recently parsed
+        // `key = value` item, so that continuation lines know where to
+        // append.
+        let mut current: Option<usize> = None;
+
+        let mut lines = contents.lines().enumerate().peekable();
+        while let Some((lineno, raw_line)) = lines.next() {
+            let line_num = lineno as u64 + 1;
+
+            // A comment is never a continuation, even when indented: check
+            // for one first so an indented `; ...`/`# ...` line is simply
+            // skipped instead of being appended to the previous value (or
+            // erroring when there's no previous value to append to).
+            let trimmed_start = raw_line.trim_start();
+            if trimmed_start.starts_with(';') || trimmed_start.starts_with('#')
+            {
+                continue;
+            }
+
+            // A line beginning with whitespace continues the previous
+            // value, as long as we actually have a previous value and the
+            // line isn't blank.
+            if is_continuation(raw_line) {
+                let trimmed = raw_line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let Some(idx) = current else {
+                    return Err(ParseError::new(
+                        path,
+                        line_num,
+                        "continuation line with no preceding item",
+                    ));
+                };
+                match &mut directives[idx] {
+                    Directive::Set { value, .. } => {
+                        value.push(' ');
+                        value.push_str(trimmed);
+                    }
+                    _ => unreachable!("`current` only ever points at a Set"),
+                }
+                continue;
+            }
+
+            let line = raw_line.trim();
+            current = None;
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#')
+            {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix('%') {
+                let mut parts = rest.splitn(2, char::is_whitespace);
+                let directive = parts.next().unwrap_or("");
+                let arg = parts.next().unwrap_or("").trim();
+                match directive {
+                    "include" => {
+                        if arg.is_empty() {
+                            return Err(ParseError::new(
+                                path,
+                                line_num,
+                                "%include with no path",
+                            ));
+                        }
+                        directives.push(Directive::Include {
+                            target: arg.to_string(),
+                            line: line_num,
+                        });
+                    }
+                    "unset" => {
+                        if arg.is_empty() {
+                            return Err(ParseError::new(
+                                path,
+                                line_num,
+                                "%unset with no key",
+                            ));
+                        }
+                        directives.push(Directive::Unset {
+                            section: section.clone(),
+                            key: arg.to_string(),
+                        });
+                    }
+                    _ => {
+                        return Err(ParseError::new(
+                            path,
+                            line_num,
+                            format!("unrecognized directive '%{directive}'"),
+                        ));
+                    }
+                }
+                continue;
+            }
+            if line.starts_with('[') {
+                let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) else {
+                    return Err(ParseError::new(
+                        path,
+                        line_num,
+                        format!("invalid section header: {line}"),
+                    ));
+                };
+                section = name.trim().to_string();
+                continue;
+            }
+            // A line with no `=` is a bare boolean flag, e.g. `smart-case`,
+            // matching the old flat config format where every line was
+            // itself a CLI flag with no value.
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+                None => (line.to_string(), String::new()),
+            };
+            if key.is_empty() {
+                return Err(ParseError::new(path, line_num, "empty key"));
+            }
+            current = Some(directives.len());
+            directives.push(Directive::Set {
+                section: section.clone(),
+                key,
+                value,
+            });
+        }
+        Ok(Layer { directives })
+    }
+}
+
+/// A line is a continuation of the previous value if it starts with
+/// whitespace; the caller is responsible for skipping a continuation line
+/// that turns out to be blank once trimmed, and for filtering out comment
+/// lines before this check ever runs.
+fn is_continuation(line: &str) -> bool {
+    line.starts_with(' ') || line.starts_with('\t')
+}
+
+/// An error encountered while parsing or merging a hierarchical config
+/// file, reporting the file and line at which it occurred.
+#[derive(Debug)]
+pub struct ParseError {
+    path: std::path::PathBuf,
+    line: Option<u64>,
+    message: String,
+}
+
+impl ParseError {
+    fn new(path: &Path, line: u64, message: impl Into<String>) -> ParseError {
+        ParseError { path: path.to_path_buf(), line: Some(line), message: message.into() }
+    }
+
+    pub(super) fn io(path: &Path, err: io::Error) -> ParseError {
+        ParseError { path: path.to_path_buf(), line: None, message: err.to_string() }
+    }
+
+    pub(super) fn include_depth_exceeded(
+        path: &Path,
+        max: u32,
+    ) -> ParseError {
+        ParseError {
+            path: path.to_path_buf(),
+            line: None,
+            message: format!("%include nesting exceeded maximum depth of {max}"),
+        }
+    }
+
+    pub(super) fn include_cycle(
+        path: &Path,
+        line: u64,
+        target: &Path,
+    ) -> ParseError {
+        ParseError {
+            path: path.to_path_buf(),
+            line: Some(line),
+            message: format!(
+                "%include cycle detected: {} is already being loaded",
+                target.display()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => {
+                write!(f, "{}:{}: {}", self.path.display(), line, self.message)
+            }
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(section: &str, key: &str, value: &str) -> Directive {
+        Directive::Set {
+            section: section.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn directives(contents: &str) -> Vec<Directive> {
+        Layer::parse(Path::new("rgrc"), contents).unwrap().directives
+    }
+
+    #[test]
+    fn top_level_items() {
+        let got = directives("smart-case\ntype-add = web:*.js\n");
+        assert_eq!(
+            got,
+            vec![set("", "smart-case", ""), set("", "type-add", "web:*.js")]
+        );
+    }
+
+    #[test]
+    fn sections() {
+        let got = directives(
+            "[search]\nsmart-case\n[output]\ncolor = always\n",
+        );
+        assert_eq!(
+            got,
+            vec![
+                set("search", "smart-case", ""),
+                set("output", "color", "always"),
+            ]
+        );
+    }
+
+    #[test]
+    fn continuation_lines() {
+        let got = directives("glob = *.rs\n  *.toml\n");
+        assert_eq!(got, vec![set("", "glob", "*.rs *.toml")]);
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        let got = directives("; a comment\n# another\nsmart-case\n");
+        assert_eq!(got, vec![set("", "smart-case", "")]);
+    }
+
+    #[test]
+    fn indented_comments_are_not_continuations() {
+        // An indented comment must be skipped outright, not appended to
+        // the preceding value as if it were a continuation line.
+        let got = directives("glob = *.rs\n  # not part of the value\n  *.toml\n");
+        assert_eq!(got, vec![set("", "glob", "*.rs *.toml")]);
+    }
+
+    #[test]
+    fn indented_comment_with_no_preceding_item_is_not_an_error() {
+        assert_eq!(directives("  ; just a comment\n"), vec![]);
+    }
+
+    #[test]
+    fn include_and_unset() {
+        let got = directives("%include other.conf\n%unset smart-case\n");
+        assert_eq!(
+            got,
+            vec![
+                Directive::Include { target: "other.conf".to_string(), line: 1 },
+                Directive::Unset { section: "".to_string(), key: "smart-case".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_section_header() {
+        assert!(Layer::parse(Path::new("rgrc"), "[search\n").is_err());
+    }
+}
@@ -0,0 +1,271 @@
+/*!
+This module implements a hierarchical configuration file format for ripgrep.
+
+Unlike the original `RIPGREP_CONFIG_PATH` format, which is just a flat list
+of one CLI flag per line, this format supports `[section]` headers,
+`key = value` items, comment lines (starting with `;` or `#`), an
+`%include <path>` directive that recursively merges another config file, and
+a `%unset <key>` directive that removes a value inherited from an earlier
+layer. The design (sections, `%include`, `%unset`, last-value-wins merging)
+is modeled closely on Mercurial's layered `ui.rc`/`layer.rs` config parser.
+
+The end result of loading a (possibly multi-file, multi-layer) config is the
+same thing the old flat loader produced: a stream of arguments that gets fed
+into the normal CLI argument parser. Sections exist purely for the user's
+organizational benefit; `[search]`, `[output]` and so on are not meaningful
+to this module, with the one exception of `[hyperlink]`, whose entries are
+handed off verbatim (as `key = value` pairs) to the hyperlink alias registry
+instead of being turned into `--flag` arguments.
+*/
+
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+mod layer;
+
+pub use self::layer::ParseError;
+
+use self::layer::{Directive, Layer};
+
+/// The maximum depth of `%include` nesting we're willing to follow before
+/// giving up. This guards against both accidental and malicious include
+/// cycles, in addition to the explicit cycle detection we also do.
+const MAX_INCLUDE_DEPTH: u32 = 10;
+
+/// The special section whose entries are not translated into `--flag`
+/// arguments, but are instead returned separately via
+/// [`Config::hyperlink_aliases`].
+const HYPERLINK_SECTION: &str = "hyperlink";
+
+/// Per-section `key = value` entries, preserving the order sections and
+/// keys were first encountered in (not sorted alphabetically), with a
+/// `Set` for a key already present in its section overwriting that key's
+/// value in place rather than appending a duplicate.
+#[derive(Clone, Debug, Default)]
+struct OrderedSections(Vec<(String, Vec<(String, String)>)>);
+
+impl OrderedSections {
+    fn section_mut(&mut self, section: &str) -> &mut Vec<(String, String)> {
+        if let Some(pos) = self.0.iter().position(|(s, _)| s == section) {
+            &mut self.0[pos].1
+        } else {
+            self.0.push((section.to_string(), vec![]));
+            &mut self.0.last_mut().expect("just pushed").1
+        }
+    }
+
+    /// Sets `key` to `value` within `section`, overwriting any value an
+    /// earlier layer set for the same key (last-value-wins) without
+    /// disturbing that key's position in the section.
+    fn set(&mut self, section: &str, key: String, value: String) {
+        let entries = self.section_mut(section);
+        match entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = value,
+            None => entries.push((key, value)),
+        }
+    }
+
+    /// Removes `key` from `section`, as if it had never been set by an
+    /// earlier layer.
+    fn unset(&mut self, section: &str, key: &str) {
+        if let Some(pos) = self.0.iter().position(|(s, _)| s == section) {
+            self.0[pos].1.retain(|(k, _)| k != key);
+        }
+    }
+
+    fn get(&self, section: &str) -> Option<&[(String, String)]> {
+        self.0
+            .iter()
+            .find(|(s, _)| s == section)
+            .map(|(_, entries)| entries.as_slice())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &(String, Vec<(String, String)>)> {
+        self.0.iter()
+    }
+}
+
+/// A fully loaded and merged configuration.
+///
+/// A `Config` is built by loading one or more layered config files (where
+/// later files, or later `%include`d files, can override or `%unset`
+/// earlier ones) and flattening the result into an ordered list of
+/// `(section, key, value)` entries.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// Entries outside of `[hyperlink]`, in first-encountered order, keyed
+    /// by section so that `%unset` can remove just the right thing. A key
+    /// set more than once (across layers) keeps only its last value.
+    sections: OrderedSections,
+}
+
+impl Config {
+    /// Load a config file from `path`, recursively merging any `%include`d
+    /// files along the way.
+    ///
+    /// This does not inspect `RIPGREP_CONFIG_PATH` or any other environment
+    /// state; the caller is responsible for locating the root config file.
+    pub fn from_path(path: &Path) -> Result<Config, ParseError> {
+        let mut merged = OrderedSections::default();
+        let mut stack = vec![path.to_path_buf()];
+        load_into(path, &mut merged, &mut stack, 0)?;
+        Ok(Config { sections: merged })
+    }
+
+    /// Returns the argument stream that should be fed into ripgrep's
+    /// argument parser, in the same form the old flat `RIPGREP_CONFIG_PATH`
+    /// loader produced: one `OsString` per flag, e.g. `--smart-case` or
+    /// `--type-add=web:*.js`.
+    ///
+    /// Entries in the `[hyperlink]` section are excluded; use
+    /// [`Config::hyperlink_aliases`] for those instead.
+    pub fn args(&self) -> Vec<OsString> {
+        let mut args = vec![];
+        for (section, entries) in self.sections.iter() {
+            if section == HYPERLINK_SECTION {
+                continue;
+            }
+            for (key, value) in entries {
+                args.push(to_flag(key, value));
+            }
+        }
+        args
+    }
+
+    /// Returns the raw `key = value` pairs found in the `[hyperlink]`
+    /// section, in merged order. The hyperlink module is responsible for
+    /// turning these into `HyperlinkAlias` values.
+    pub fn hyperlink_aliases(&self) -> &[(String, String)] {
+        self.sections.get(HYPERLINK_SECTION).unwrap_or(&[])
+    }
+}
+
+/// Turn a `key = value` config entry into the equivalent long CLI flag.
+///
+/// An empty value is treated as a boolean switch (`--key`) rather than
+/// `--key=`, since that's how most of ripgrep's boolean flags are spelled
+/// in the flat config format today.
+fn to_flag(key: &str, value: &str) -> OsString {
+    if value.is_empty() {
+        OsString::from(format!("--{key}"))
+    } else {
+        OsString::from(format!("--{key}={value}"))
+    }
+}
+
+/// Parse the file at `path` and fold its directives into `merged`,
+/// recursively following `%include` directives as they're encountered.
+///
+/// `stack` tracks the chain of files currently being loaded, for cycle
+/// detection; `depth` is the current include depth, for the depth limit.
+fn load_into(
+    path: &Path,
+    merged: &mut OrderedSections,
+    stack: &mut Vec<PathBuf>,
+    depth: u32,
+) -> Result<(), ParseError> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(ParseError::include_depth_exceeded(path, MAX_INCLUDE_DEPTH));
+    }
+    let contents = fs::read_to_string(path)
+        .map_err(|err| ParseError::io(path, err))?;
+    let layer = Layer::parse(path, &contents)?;
+    for directive in layer.directives {
+        match directive {
+            Directive::Set { section, key, value } => {
+                merged.set(&section, key, value);
+            }
+            Directive::Unset { section, key } => {
+                merged.unset(&section, &key);
+            }
+            Directive::Include { target, line } => {
+                let resolved = resolve_include(path, &target);
+                if stack.contains(&resolved) {
+                    return Err(ParseError::include_cycle(path, line, &resolved));
+                }
+                stack.push(resolved.clone());
+                load_into(&resolved, merged, stack, depth + 1)?;
+                stack.pop();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve an `%include` target relative to the file that contains it.
+fn resolve_include(from: &Path, target: &str) -> PathBuf {
+    let target = Path::new(target);
+    if target.is_absolute() {
+        return target.to_path_buf();
+    }
+    match from.parent() {
+        Some(parent) => parent.join(target),
+        None => target.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn config_from(contents: &str) -> Config {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!(
+            "ripgrep-config-test-{:?}.rgrc",
+            std::thread::current().id()
+        ));
+        fs::write(&path, contents).unwrap();
+        let config = Config::from_path(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        config
+    }
+
+    fn args_of(config: &Config) -> Vec<String> {
+        config
+            .args()
+            .iter()
+            .map(|a| a.to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn args_preserve_load_order_not_alphabetical() {
+        let config = config_from("[search]\nsmart-case\n[output]\ncolor = always\n");
+        assert_eq!(
+            args_of(&config),
+            vec!["--smart-case".to_string(), "--color=always".to_string()],
+        );
+    }
+
+    #[test]
+    fn later_set_overrides_without_duplicating() {
+        let config = config_from("color = never\ncolor = always\n");
+        assert_eq!(args_of(&config), vec!["--color=always".to_string()]);
+    }
+
+    #[test]
+    fn unset_removes_earlier_value() {
+        let config = config_from("smart-case\n%unset smart-case\n");
+        assert!(args_of(&config).is_empty());
+    }
+
+    #[test]
+    fn hyperlink_section_excluded_from_args() {
+        let config = config_from("[hyperlink]\nsublime = subl://{path}:{line}\n");
+        assert!(args_of(&config).is_empty());
+        assert_eq!(
+            config.hyperlink_aliases(),
+            &[("sublime".to_string(), "subl://{path}:{line}".to_string())],
+        );
+    }
+
+    #[test]
+    fn to_flag_handles_empty_and_nonempty_values() {
+        assert_eq!(to_flag("smart-case", ""), OsStr::new("--smart-case"));
+        assert_eq!(to_flag("color", "always"), OsStr::new("--color=always"));
+    }
+}
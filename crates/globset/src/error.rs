@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// An error that can occur when building a [`crate::Glob`] or
+/// [`crate::GlobSet`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    glob: Option<String>,
+    message: String,
+}
+
+impl Error {
+    pub(crate) fn new(message: impl Into<String>) -> Error {
+        Error { glob: None, message: message.into() }
+    }
+
+    pub(crate) fn for_glob(mut self, glob: &str) -> Error {
+        self.glob = Some(glob.to_string());
+        self
+    }
+
+    /// The original glob pattern that produced this error, if available.
+    pub fn glob(&self) -> Option<&str> {
+        self.glob.as_deref()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.glob {
+            Some(glob) => write!(f, "error parsing glob '{glob}': {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,416 @@
+//! This module implements [`Glob`], a single glob pattern.
+//!
+//! In addition to the usual `*`, `?` and `[...]` wildcards, a `Glob`
+//! supports brace expansion (e.g. `src/{foo,bar}/*.rs`, including nested
+//! and empty alternatives like `{,.backup}`) and extglob-style alternation
+//! groups (e.g. `src/@(foo|bar)/*.rs`). Both forms are expanded at
+//! construction time into a set of concrete alternative patterns; a path
+//! matches the `Glob` if it matches *any* alternative. Crucially,
+//! [`Glob::glob`] always returns the exact string the `Glob` was built
+//! from, so `Glob::new(s).glob() == s` holds regardless of how many
+//! alternatives a brace or extglob group expands to (this is asserted by
+//! `fuzz/fuzz_targets/fuzz_glob.rs`).
+
+use std::{fmt, str::FromStr};
+
+use crate::Error;
+
+/// A guard against pathological or adversarial input like `{a,b}` repeated
+/// thirteen times in a row (2^13 = 8192 alternatives), which would
+/// otherwise expand to an unreasonable number of alternatives.
+const MAX_ALTERNATIVES: usize = 4096;
+
+/// A guard against unbounded recursion during expansion. Every brace/extglob
+/// group expanded adds one level, whether the groups are nested or appear
+/// one after another, so this bounds the total number of groups along any
+/// expansion path.
+const MAX_GROUP_DEPTH: u32 = 64;
+
+/// A single glob pattern, e.g. `*.rs` or `src/{foo,bar}/*.rs`.
+///
+/// A `Glob` is immutable once constructed. Use [`Glob::new`] (or the
+/// [`FromStr`] impl) to parse one, [`Glob::is_match`] to test it against a
+/// path, and [`Glob::alternatives`] to see the concrete patterns a brace or
+/// extglob group expanded into.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Glob {
+    original: String,
+    alternatives: Vec<String>,
+}
+
+impl Glob {
+    /// Parses a glob pattern, expanding any `{...,...}` brace groups or
+    /// `@(...|...)` extglob alternation groups it contains.
+    ///
+    /// Returns an error if the pattern contains unbalanced group
+    /// delimiters or expands into an unreasonable number of alternatives.
+    pub fn new(glob: &str) -> Result<Glob, Error> {
+        let alternatives = expand(glob).map_err(|e| e.for_glob(glob))?;
+        Ok(Glob { original: glob.to_string(), alternatives })
+    }
+
+    /// Returns the exact pattern string this `Glob` was constructed from,
+    /// braces and all. This always round-trips: `Glob::new(s)?.glob() ==
+    /// s` for every `s` that parses successfully.
+    pub fn glob(&self) -> &str {
+        &self.original
+    }
+
+    /// Returns the concrete patterns that the original pattern's brace and
+    /// extglob groups expanded into. For a pattern with no such groups,
+    /// this is a single-element slice equal to [`Glob::glob`].
+    pub fn alternatives(&self) -> &[String] {
+        &self.alternatives
+    }
+
+    /// Returns true if `path` matches this glob, i.e. if it matches *any*
+    /// of [`Glob::alternatives`].
+    pub fn is_match(&self, path: impl AsRef<str>) -> bool {
+        let path = path.as_ref();
+        self.alternatives.iter().any(|alt| glob_match(alt, path))
+    }
+}
+
+impl FromStr for Glob {
+    type Err = Error;
+
+    fn from_str(glob: &str) -> Result<Glob, Error> {
+        Glob::new(glob)
+    }
+}
+
+impl fmt::Display for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.original)
+    }
+}
+
+/// Expands every top-level `{...}` and `@(...)` group in `pattern`,
+/// returning the cartesian product of their alternatives as a flat list of
+/// concrete patterns (which may themselves still contain plain `*`/`?`/
+/// `[...]` wildcards, but no brace or extglob syntax).
+fn expand(pattern: &str) -> Result<Vec<String>, Error> {
+    expand_depth(pattern, 0)
+}
+
+fn expand_depth(pattern: &str, depth: u32) -> Result<Vec<String>, Error> {
+    if depth > MAX_GROUP_DEPTH {
+        return Err(Error::new("too many brace/extglob groups"));
+    }
+    let Some(group) = find_group(pattern)? else {
+        return Ok(vec![pattern.to_string()]);
+    };
+    let prefix = &pattern[..group.group_start];
+    let inner = &pattern[group.inner_start..group.inner_end];
+    let suffix = &pattern[group.close + 1..];
+
+    let mut out = vec![];
+    for alt in split_top_level(inner, group.separator) {
+        let combined = format!("{prefix}{alt}{suffix}");
+        for expanded in expand_depth(&combined, depth + 1)? {
+            out.push(expanded);
+            if out.len() > MAX_ALTERNATIVES {
+                return Err(Error::new(format!(
+                    "glob expands to more than {MAX_ALTERNATIVES} alternatives"
+                )));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// The location and kind of the first top-level brace or extglob group
+/// found in a pattern.
+struct Group {
+    /// Byte offset of the group's opening delimiter (`{` or `@`).
+    group_start: usize,
+    /// Byte offset just after the opening delimiter, where the group's
+    /// contents begin.
+    inner_start: usize,
+    /// Byte offset where the group's contents end (exclusive).
+    inner_end: usize,
+    /// Byte offset of the group's closing delimiter (`}` or `)`).
+    close: usize,
+    /// The character that separates alternatives within the group: `,`
+    /// for brace groups, `|` for extglob groups.
+    separator: char,
+}
+
+/// Finds the leftmost top-level (i.e. not nested inside another group)
+/// brace or extglob group in `pattern`.
+fn find_group(pattern: &str) -> Result<Option<Group>, Error> {
+    let bytes = pattern.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'{' {
+            let close = find_matching(bytes, i, b'{', b'}')
+                .ok_or_else(|| Error::new("unbalanced '{' in glob"))?;
+            return Ok(Some(Group {
+                group_start: i,
+                inner_start: i + 1,
+                inner_end: close,
+                close,
+                separator: ',',
+            }));
+        }
+        // A `}` with no preceding `{` to open it can't be part of a brace
+        // group. Reject it outright instead of letting it flow through
+        // into an alternative, where it would look like (but not be) a
+        // brace group to a later expansion pass.
+        if bytes[i] == b'}' {
+            return Err(Error::new("unmatched '}' in glob"));
+        }
+        if bytes[i] == b'@' && bytes.get(i + 1) == Some(&b'(') {
+            let close = find_matching(bytes, i + 1, b'(', b')')
+                .ok_or_else(|| Error::new("unbalanced '@(' in glob"))?;
+            return Ok(Some(Group {
+                group_start: i,
+                inner_start: i + 2,
+                inner_end: close,
+                close,
+                separator: '|',
+            }));
+        }
+        i += 1;
+    }
+    Ok(None)
+}
+
+/// Given the index of an opening delimiter, finds the index of its
+/// matching closing delimiter, accounting for nested occurrences of the
+/// same delimiter pair.
+fn find_matching(
+    bytes: &[u8],
+    open_idx: usize,
+    open: u8,
+    close: u8,
+) -> Option<usize> {
+    let mut depth = 0i32;
+    for (offset, &b) in bytes[open_idx..].iter().enumerate() {
+        if b == open {
+            depth += 1;
+        } else if b == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(open_idx + offset);
+            }
+        }
+    }
+    None
+}
+
+/// Splits `s` on `sep`, but only at the top level: occurrences of `sep`
+/// nested inside a `{...}` or `(...)` group are not treated as
+/// separators. This lets e.g. `{foo,@(bar,baz)}` nest correctly.
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' | '(' => depth += 1,
+            '}' | ')' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// A small, dependency-free shell-style wildcard matcher used to test a
+/// single expanded (brace/extglob-free) pattern against a path. Supports
+/// `*` (any run of characters other than `/`), `?` (any single character),
+/// and `[...]`/`[!...]` character classes. `*` is separator-aware (like
+/// shell globbing) so that e.g. `src/{foo,bar}/*.rs` doesn't also match
+/// `src/foo/a/b.rs`; spanning `/` requires writing `**` or repeating `*/`
+/// explicitly in the pattern.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn glob_match_bytes(pat: &[u8], text: &[u8]) -> bool {
+    match pat.first() {
+        None => text.is_empty(),
+        Some(b'*') if pat.get(1) == Some(&b'*') => {
+            glob_match_bytes(&pat[2..], text)
+                || (!text.is_empty() && glob_match_bytes(pat, &text[1..]))
+        }
+        Some(b'*') => {
+            glob_match_bytes(&pat[1..], text)
+                || (!text.is_empty()
+                    && text[0] != b'/'
+                    && glob_match_bytes(pat, &text[1..]))
+        }
+        Some(b'?') => {
+            !text.is_empty() && glob_match_bytes(&pat[1..], &text[1..])
+        }
+        Some(b'[') => match match_class(pat, text) {
+            Some((pat_rest, text_rest)) => glob_match_bytes(pat_rest, text_rest),
+            None => false,
+        },
+        Some(&pc) => {
+            !text.is_empty()
+                && text[0] == pc
+                && glob_match_bytes(&pat[1..], &text[1..])
+        }
+    }
+}
+
+/// Matches a leading `[...]` or `[!...]` character class in `pat` against
+/// the first byte of `text`, returning the remaining slices of each on
+/// success.
+fn match_class<'p, 't>(
+    pat: &'p [u8],
+    text: &'t [u8],
+) -> Option<(&'p [u8], &'t [u8])> {
+    let Some(&c) = text.first() else { return None };
+    let mut i = 1; // skip the leading '['
+    let negate = matches!(pat.get(i), Some(b'!') | Some(b'^'));
+    if negate {
+        i += 1;
+    }
+    let class_start = i;
+    while pat.get(i).is_some_and(|&b| b != b']') {
+        i += 1;
+    }
+    let class_end = i; // index of ']', or pat.len() if unterminated
+    if pat.get(class_end) != Some(&b']') {
+        return None;
+    }
+    let class = &pat[class_start..class_end];
+    let found = class_contains(class, c);
+    if found == !negate {
+        Some((&pat[class_end + 1..], &text[1..]))
+    } else {
+        None
+    }
+}
+
+/// Returns true if `c` is a member of a `[...]` class body, honoring
+/// `a-z`-style ranges in addition to individual characters.
+fn class_contains(class: &[u8], c: u8) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        if class[i + 1..].first() == Some(&b'-') && i + 2 < class.len() {
+            let (lo, hi) = (class[i], class[i + 2]);
+            if lo <= c && c <= hi {
+                return true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alts(pattern: &str) -> Vec<String> {
+        Glob::new(pattern).unwrap().alternatives().to_vec()
+    }
+
+    #[test]
+    fn round_trips_plain_glob() {
+        let g = Glob::new("src/*.rs").unwrap();
+        assert_eq!(g.glob(), "src/*.rs");
+        assert_eq!(g.alternatives(), &["src/*.rs".to_string()]);
+    }
+
+    #[test]
+    fn round_trips_braced_glob() {
+        let g = Glob::new("src/{foo,bar}/*.rs").unwrap();
+        assert_eq!(g.glob(), "src/{foo,bar}/*.rs");
+    }
+
+    #[test]
+    fn expands_simple_braces() {
+        assert_eq!(
+            alts("src/{foo,bar}/*.rs"),
+            vec!["src/foo/*.rs".to_string(), "src/bar/*.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn expands_empty_alternative() {
+        assert_eq!(
+            alts("config{,.backup}"),
+            vec!["config".to_string(), "config.backup".to_string()]
+        );
+    }
+
+    #[test]
+    fn expands_nested_braces() {
+        assert_eq!(
+            alts("{a,b{c,d}}"),
+            vec!["a".to_string(), "bc".to_string(), "bd".to_string()]
+        );
+    }
+
+    #[test]
+    fn expands_multiple_groups() {
+        let got = alts("{a,b}-{1,2}");
+        assert_eq!(
+            got,
+            vec![
+                "a-1".to_string(),
+                "a-2".to_string(),
+                "b-1".to_string(),
+                "b-2".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_extglob_alternation() {
+        assert_eq!(
+            alts("src/@(foo|bar)/*.rs"),
+            vec!["src/foo/*.rs".to_string(), "src/bar/*.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn unbalanced_brace_is_an_error() {
+        assert!(Glob::new("src/{foo/*.rs").is_err());
+    }
+
+    #[test]
+    fn stray_closing_brace_is_an_error() {
+        assert!(Glob::new("}").is_err());
+        assert!(Glob::new("x}y").is_err());
+        assert!(Glob::new("{a}b}").is_err());
+    }
+
+    #[test]
+    fn matches_any_alternative() {
+        let g = Glob::new("src/{foo,bar}/*.rs").unwrap();
+        assert!(g.is_match("src/foo/lib.rs"));
+        assert!(g.is_match("src/bar/lib.rs"));
+        assert!(!g.is_match("src/baz/lib.rs"));
+    }
+
+    #[test]
+    fn matches_wildcards_and_classes() {
+        let g = Glob::new("[a-c]*.rs").unwrap();
+        assert!(g.is_match("a.rs"));
+        assert!(g.is_match("bfoo.rs"));
+        assert!(!g.is_match("d.rs"));
+    }
+
+    #[test]
+    fn star_does_not_cross_path_separator() {
+        let g = Glob::new("src/{foo,bar}/*.rs").unwrap();
+        assert!(g.is_match("src/foo/lib.rs"));
+        assert!(g.is_match("src/bar/lib.rs"));
+        assert!(!g.is_match("src/foo/a/b.rs"));
+    }
+}
@@ -0,0 +1,15 @@
+/*!
+A small globbing library supporting brace expansion (`{foo,bar}`) and
+extglob-style alternation (`@(foo|bar)`), in addition to the usual `*`,
+`?`, and `[...]` wildcards. See [`Glob`] and [`GlobSet`].
+*/
+
+mod error;
+mod glob;
+mod set;
+
+pub use crate::{
+    error::Error,
+    glob::Glob,
+    set::{GlobSet, GlobSetBuilder},
+};
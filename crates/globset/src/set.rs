@@ -0,0 +1,83 @@
+use crate::{Error, Glob};
+
+/// A set of globs that can be matched against a path in one pass, built
+/// from a [`GlobSetBuilder`].
+///
+/// A path matches a `GlobSet` if it matches at least one of the globs it
+/// was built from. For a braced glob like `src/{foo,bar}/*.rs`, that in
+/// turn means matching any of its expanded alternatives.
+#[derive(Clone, Debug, Default)]
+pub struct GlobSet {
+    globs: Vec<Glob>,
+}
+
+impl GlobSet {
+    /// Returns true if `path` matches any glob in this set.
+    pub fn is_match(&self, path: impl AsRef<str>) -> bool {
+        let path = path.as_ref();
+        self.globs.iter().any(|g| g.is_match(path))
+    }
+
+    /// Returns true if this set contains no globs.
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_empty()
+    }
+
+    /// The number of globs in this set (counting each braced glob once,
+    /// regardless of how many alternatives it expanded into).
+    pub fn len(&self) -> usize {
+        self.globs.len()
+    }
+}
+
+/// Builds a [`GlobSet`] from a collection of [`Glob`]s.
+#[derive(Clone, Debug, Default)]
+pub struct GlobSetBuilder {
+    globs: Vec<Glob>,
+}
+
+impl GlobSetBuilder {
+    /// Creates a new, empty builder.
+    pub fn new() -> GlobSetBuilder {
+        GlobSetBuilder { globs: vec![] }
+    }
+
+    /// Adds a glob to this builder's set.
+    pub fn add(&mut self, glob: Glob) -> &mut GlobSetBuilder {
+        self.globs.push(glob);
+        self
+    }
+
+    /// Builds the [`GlobSet`].
+    ///
+    /// This never actually fails today (each `Glob` was already validated
+    /// by [`Glob::new`]), but returns a `Result` to leave room for
+    /// compiled-representation validation without breaking callers.
+    pub fn build(&self) -> Result<GlobSet, Error> {
+        Ok(GlobSet { globs: self.globs.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_if_any_glob_matches() {
+        let mut builder = GlobSetBuilder::new();
+        builder.add(Glob::new("*.rs").unwrap());
+        builder.add(Glob::new("src/{foo,bar}/*.toml").unwrap());
+        let set = builder.build().unwrap();
+
+        assert!(set.is_match("lib.rs"));
+        assert!(set.is_match("src/foo/Cargo.toml"));
+        assert!(!set.is_match("src/baz/Cargo.toml"));
+    }
+
+    #[test]
+    fn empty_set_matches_nothing() {
+        let set = GlobSetBuilder::new().build().unwrap();
+        assert!(set.is_empty());
+        assert!(!set.is_match("anything"));
+    }
+}
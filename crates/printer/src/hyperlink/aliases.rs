@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::hyperlink::HyperlinkAlias;
 
 /// Aliases to well-known hyperlink schemes.
@@ -31,7 +33,11 @@ pub(super) const HYPERLINK_PATTERN_ALIASES: &[HyperlinkAlias] = &[
 
 /// Creates a [`HyperlinkAlias`].
 const fn alias(name: &'static str, format: &'static str) -> HyperlinkAlias {
-    HyperlinkAlias { name, format, display_priority: None }
+    HyperlinkAlias {
+        name: Cow::Borrowed(name),
+        format: Cow::Borrowed(format),
+        display_priority: None,
+    }
 }
 
 /// Creates a [`HyperlinkAlias`] with a display priority.
@@ -40,5 +46,9 @@ const fn prioritized_alias(
     name: &'static str,
     format: &'static str,
 ) -> HyperlinkAlias {
-    HyperlinkAlias { name, format, display_priority: Some(priority) }
+    HyperlinkAlias {
+        name: Cow::Borrowed(name),
+        format: Cow::Borrowed(format),
+        display_priority: Some(priority),
+    }
 }
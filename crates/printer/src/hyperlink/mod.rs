@@ -0,0 +1,226 @@
+/*!
+Support for resolving `--hyperlink-format` aliases.
+
+In addition to ripgrep's built-in aliases (`vscode`, `kitty`, `textmate`,
+...), this module lets callers register their own [`HyperlinkAlias`]
+values, for example ones loaded from the `[hyperlink]` section of a config
+file or passed in via the environment. User-supplied aliases participate in
+`--hyperlink-format=<alias>` resolution exactly like the built-ins: the
+combined set is kept sorted by name, and a user alias with the same name as
+a built-in replaces it.
+
+This module also implements `--hyperlink-format=auto`, which inspects the
+environment to guess the best matching built-in format for the terminal
+ripgrep is running in.
+*/
+
+use std::{borrow::Cow, env};
+
+mod aliases;
+
+use self::aliases::HYPERLINK_PATTERN_ALIASES;
+
+/// A named hyperlink format: a `name` (as used in `--hyperlink-format`), a
+/// `format` string containing `{path}`/`{host}`/`{line}`/`{column}`
+/// placeholders, and an optional `display_priority` used to break ties
+/// when multiple aliases share a name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HyperlinkAlias {
+    pub(super) name: Cow<'static, str>,
+    pub(super) format: Cow<'static, str>,
+    pub(super) display_priority: Option<i16>,
+}
+
+impl HyperlinkAlias {
+    /// Creates a new hyperlink alias, for example one parsed out of a
+    /// config file's `[hyperlink]` section or supplied via the
+    /// environment.
+    pub fn new(
+        name: impl Into<String>,
+        format: impl Into<String>,
+        display_priority: Option<i16>,
+    ) -> HyperlinkAlias {
+        HyperlinkAlias {
+            name: Cow::Owned(name.into()),
+            format: Cow::Owned(format.into()),
+            display_priority,
+        }
+    }
+
+    /// The name used to select this alias via `--hyperlink-format`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The format string, e.g. `vscode://file{path}:{line}:{column}`.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+}
+
+/// Merges `custom` aliases into ripgrep's built-in table, returning the
+/// combined set sorted by name. A custom alias always takes precedence
+/// over a built-in of the same name (the built-in is dropped). Two custom
+/// aliases are allowed to share a name; ties in name are broken by
+/// `display_priority` (lower sorts first, and thus wins under
+/// [`resolve`]; an unset priority sorts last).
+pub fn merge_aliases(custom: Vec<HyperlinkAlias>) -> Vec<HyperlinkAlias> {
+    let mut merged: Vec<HyperlinkAlias> =
+        HYPERLINK_PATTERN_ALIASES.to_vec();
+    for alias in &custom {
+        // Only built-ins are replaced outright here; two custom aliases
+        // sharing a name both survive and are ordered by priority below,
+        // so that the priority tie-break actually has something to do.
+        merged.retain(|a| a.name != alias.name);
+    }
+    merged.extend(custom);
+    merged.sort_by(|a, b| {
+        a.name.cmp(&b.name).then_with(|| {
+            let pa = a.display_priority.unwrap_or(i16::MAX);
+            let pb = b.display_priority.unwrap_or(i16::MAX);
+            pa.cmp(&pb)
+        })
+    });
+    merged
+}
+
+/// Looks up `name` in `aliases` (normally the result of
+/// [`merge_aliases`]), returning its format string. When more than one
+/// alias shares `name`, the one sorted first by `display_priority` wins,
+/// since [`merge_aliases`] sorts ties into that order.
+pub fn resolve<'a>(
+    aliases: &'a [HyperlinkAlias],
+    name: &str,
+) -> Option<&'a str> {
+    aliases.iter().find(|a| a.name == name).map(|a| a.format.as_ref())
+}
+
+/// Implements `--hyperlink-format=auto` by inspecting environment
+/// variables to guess which built-in alias best matches the terminal
+/// ripgrep is running in. Falls back to `"default"` when nothing more
+/// specific is recognized.
+///
+/// `get` abstracts over `std::env::var` so this is testable without
+/// mutating the real process environment.
+pub fn detect_from_env(get: impl Fn(&str) -> Option<String>) -> &'static str {
+    if let Some(term_program) = get("TERM_PROGRAM") {
+        match term_program.as_str() {
+            "vscode" => {
+                let is_insiders = get("TERM_PROGRAM_VERSION")
+                    .map(|v| v.contains("insider"))
+                    .unwrap_or(false);
+                return if is_insiders { "vscode-insiders" } else { "vscode" };
+            }
+            _ => {}
+        }
+    }
+    if get("KITTY_WINDOW_ID").is_some() {
+        return "kitty";
+    }
+    if get("VTE_VERSION").is_some() {
+        // VTE-based terminals (GNOME Terminal, Guake, Tilix, ...) already
+        // understand the plain `file://` scheme used by "default"; we
+        // don't have a more specific built-in alias for any of them.
+        return "default";
+    }
+    if get("WSL_DISTRO_NAME").is_some() || get("WSLENV").is_some() {
+        // WSL's default Windows terminal doesn't reliably support any of
+        // our editor-specific URL schemes, so fall back to `file://`.
+        return "default";
+    }
+    if get("SSH_CONNECTION").is_some() || get("SSH_TTY").is_some() {
+        // A hyperlink generated over an SSH session points at a path on
+        // the remote machine, so don't guess an editor-specific scheme
+        // that assumes the link is opened locally.
+        return "default";
+    }
+    "default"
+}
+
+/// Like [`detect_from_env`], but reads from the real process environment.
+pub fn detect() -> &'static str {
+    detect_from_env(|key| env::var(key).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = pairs
+            .iter()
+            .map(|&(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key: &str| map.get(key).cloned()
+    }
+
+    #[test]
+    fn detects_vscode() {
+        let env = env_of(&[("TERM_PROGRAM", "vscode")]);
+        assert_eq!(detect_from_env(env), "vscode");
+    }
+
+    #[test]
+    fn detects_vscode_insiders() {
+        let env = env_of(&[
+            ("TERM_PROGRAM", "vscode"),
+            ("TERM_PROGRAM_VERSION", "1.2.3-insider"),
+        ]);
+        assert_eq!(detect_from_env(env), "vscode-insiders");
+    }
+
+    #[test]
+    fn detects_kitty() {
+        let env = env_of(&[("KITTY_WINDOW_ID", "1")]);
+        assert_eq!(detect_from_env(env), "kitty");
+    }
+
+    #[test]
+    fn falls_back_to_default() {
+        assert_eq!(detect_from_env(env_of(&[])), "default");
+    }
+
+    #[test]
+    fn detects_vte_as_default() {
+        let env = env_of(&[("VTE_VERSION", "6800")]);
+        assert_eq!(detect_from_env(env), "default");
+    }
+
+    #[test]
+    fn custom_alias_overrides_builtin() {
+        let custom =
+            vec![HyperlinkAlias::new("vscode", "myvscode://{path}", None)];
+        let merged = merge_aliases(custom);
+        assert_eq!(resolve(&merged, "vscode"), Some("myvscode://{path}"));
+    }
+
+    #[test]
+    fn merge_keeps_sorted_by_name() {
+        let custom =
+            vec![HyperlinkAlias::new("alpha", "alpha://{path}", None)];
+        let merged = merge_aliases(custom);
+        let names: Vec<&str> = merged.iter().map(|a| a.name()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn unknown_name_does_not_resolve() {
+        let merged = merge_aliases(vec![]);
+        assert_eq!(resolve(&merged, "does-not-exist"), None);
+    }
+
+    #[test]
+    fn same_name_custom_aliases_resolve_by_priority() {
+        let custom = vec![
+            HyperlinkAlias::new("review", "lowpri://{path}", Some(5)),
+            HyperlinkAlias::new("review", "highpri://{path}", Some(-5)),
+        ];
+        let merged = merge_aliases(custom);
+        // The lower display_priority wins the tie, regardless of
+        // registration order.
+        assert_eq!(resolve(&merged, "review"), Some("highpri://{path}"));
+    }
+}
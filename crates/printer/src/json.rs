@@ -0,0 +1,156 @@
+/*!
+Defines the JSON message types emitted by ripgrep's `--json` printer.
+
+Each line of `--json` output is a single JSON object tagged by `"type"`,
+corresponding to one variant of [`Message`]. This module only defines the
+wire format; the printer that drives it (buffering matches, tracking
+stats, deciding when to flush a `Message::End`) lives alongside it and is
+out of scope here.
+*/
+
+use serde::Serialize;
+
+/// A single `--json` output message.
+#[derive(Serialize)]
+#[serde(tag = "type")]
+#[serde(rename_all = "lowercase")]
+pub enum Message<'a> {
+    Begin(Begin<'a>),
+    Match(Match<'a>),
+    Context(Context<'a>),
+    End(End<'a>),
+    Summary(Summary<'a>),
+    /// Emitted whenever ripgrep's binary-data detector fires on a file,
+    /// i.e. whenever it would otherwise print one of the human-readable
+    /// `binary file matches (found "\0" byte around offset N)` or
+    /// `WARNING: stopped searching binary file after match` notices.
+    ///
+    /// Unlike those notices, this carries enough structure for a machine
+    /// consumer to distinguish "no matches" from "the search was aborted
+    /// partway through because the file turned out to be binary" --
+    /// exactly the ambiguity demonstrated by ripgrep's `-l` vs `-c`
+    /// disagreement on binary files.
+    BinaryDetected(BinaryDetected<'a>),
+}
+
+/// A textual or raw-bytes value, used anywhere a path or line of text
+/// might not be valid UTF-8.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Data<'a> {
+    Text { text: &'a str },
+    Bytes { bytes: &'a [u8] },
+}
+
+impl<'a> Data<'a> {
+    pub fn text(text: &'a str) -> Data<'a> {
+        Data::Text { text }
+    }
+
+    pub fn bytes(bytes: &'a [u8]) -> Data<'a> {
+        Data::Bytes { bytes }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Begin<'a> {
+    pub path: Option<Data<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct Match<'a> {
+    pub path: Option<Data<'a>>,
+    pub lines: Data<'a>,
+    pub line_number: Option<u64>,
+    pub absolute_offset: u64,
+}
+
+#[derive(Serialize)]
+pub struct Context<'a> {
+    pub path: Option<Data<'a>>,
+    pub lines: Data<'a>,
+    pub line_number: Option<u64>,
+    pub absolute_offset: u64,
+}
+
+#[derive(Serialize)]
+pub struct End<'a> {
+    pub path: Option<Data<'a>>,
+    pub binary_offset: Option<u64>,
+    pub stats: Stats,
+}
+
+#[derive(Serialize)]
+pub struct Summary<'a> {
+    pub elapsed_total: Data<'a>,
+    pub stats: Stats,
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub matches: u64,
+    pub matched_lines: u64,
+}
+
+/// Structured metadata about a single binary-detection event, mirroring
+/// the information already computed to build the text notices but
+/// otherwise invisible to machine consumers.
+#[derive(Serialize)]
+pub struct BinaryDetected<'a> {
+    /// The file the binary data was detected in, or `None` when searching
+    /// stdin.
+    pub path: Option<Data<'a>>,
+    /// The byte offset of the NUL (or other configured binary-detection)
+    /// byte that triggered detection.
+    pub offset: u64,
+    /// True if matches after `offset` exist in the file but were skipped
+    /// because the search stopped at `offset`. This is what separates
+    /// "no matches" from "the search was truncated" for a consumer that
+    /// otherwise can't tell the two apart.
+    pub truncated: bool,
+    /// True if the file was searched because the user named it directly
+    /// on the command line (or via stdin), false if it was discovered via
+    /// recursive directory traversal. Ripgrep's binary-detection notices
+    /// already distinguish these two cases (compare the `hay: WARNING:
+    /// ...` and bare `binary file matches ...` notice forms); this field
+    /// surfaces the same distinction as structured data.
+    pub explicit: bool,
+}
+
+impl<'a> BinaryDetected<'a> {
+    pub fn new(
+        path: Option<Data<'a>>,
+        offset: u64,
+        truncated: bool,
+        explicit: bool,
+    ) -> BinaryDetected<'a> {
+        BinaryDetected { path, offset, truncated, explicit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_detected_serializes_with_type_tag() {
+        let msg = Message::BinaryDetected(BinaryDetected::new(
+            Some(Data::text("hay")),
+            77041,
+            true,
+            false,
+        ));
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"binarydetected\""));
+        assert!(json.contains("\"offset\":77041"));
+        assert!(json.contains("\"truncated\":true"));
+        assert!(json.contains("\"explicit\":false"));
+    }
+
+    #[test]
+    fn binary_detected_path_is_null_on_stdin() {
+        let msg = BinaryDetected::new(None, 0, false, true);
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"path\":null"));
+    }
+}
@@ -18,5 +18,21 @@ libfuzzer_sys::fuzz_target!(|glob_str: &str| {
     assert_eq!(glob, glob2);
 
     // Verify that `Glob::glob` produces the same string as the original.
+    // This must hold even when `glob_str` contains brace (`{foo,bar}`) or
+    // extglob (`@(foo|bar)`) syntax: those are expanded into
+    // `Glob::alternatives` internally, but the original pattern string is
+    // always preserved verbatim.
     assert_eq!(glob.glob(), glob_str);
+
+    // The two construction paths must also agree on what they expanded
+    // the pattern into.
+    assert_eq!(glob.alternatives(), glob2.alternatives());
+
+    // None of the expanded alternatives should themselves contain brace or
+    // extglob syntax; expansion must fully resolve every group.
+    for alt in glob.alternatives() {
+        assert!(
+            !alt.contains('{') && !alt.contains('}') && !alt.contains("@(")
+        );
+    }
 });
@@ -466,3 +466,33 @@ rgtest!(
         eqnice!("file1.txt:1\nfile2.txt:1\n", got);
     }
 );
+
+// See: https://github.com/BurntSushi/ripgrep/issues/3131
+//
+// Same setup as `matching_files_inconsistent_with_count` above, but with
+// --binary-consistent passed to -l: it now fully scans file1.txt for the
+// NUL byte before reporting it as a match, so it agrees with what -c
+// already reports (see crates/core/binary_consistent.rs).
+rgtest!(
+    binary_consistent_agrees_with_count,
+    |dir: Dir, _cmd: TestCommand| {
+        let mut file1 = String::new();
+        file1.push_str("cat here\n");
+        for _ in 0..150_000 {
+            file1.push_str("padding line\n");
+        }
+        file1.push_str("\x00");
+
+        dir.create("file1.txt", &file1);
+        dir.create("file2.txt", "cat here");
+
+        let got = dir
+            .command()
+            .args(&["--sort=path", "--binary-consistent", "-l", "cat"])
+            .stdout();
+        eqnice!("file2.txt\n", got);
+
+        let got = dir.command().args(&["--sort=path", "-c", "cat"]).stdout();
+        eqnice!("file2.txt:1\n", got);
+    }
+);